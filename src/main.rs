@@ -1,8 +1,9 @@
 #![feature(portable_simd)]
 
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use rayon::prelude::*;
 use clap::Parser;
 use std::simd::{Simd};
@@ -10,33 +11,43 @@ use std::simd::prelude::*;
 use ocl::{Platform, Device, Context, Queue, Program, Buffer, Kernel, flags};
 
 
-#[inline]
-fn apply_rule(val: u8) -> u8 {
-    match val {
-        7 | 4 | 0 => 0,
-        _ => 1,
+/// Number of distinct 3-cell neighborhoods for an elementary CA.
+const LUT_SIZE: usize = 8;
+
+/// Build the 8-entry transition lookup table for a Wolfram elementary CA
+/// rule number: `lut[code] = (rule >> code) & 1`.
+fn build_lut(rule: u8) -> [u8; LUT_SIZE] {
+    let mut lut = [0u8; LUT_SIZE];
+    for (code, slot) in lut.iter_mut().enumerate() {
+        *slot = (rule >> code) & 1;
     }
+    lut
+}
+
+#[inline]
+fn apply_rule(lut: &[u8; LUT_SIZE], val: u8) -> u8 {
+    lut[val as usize]
 }
 
-fn simulate_rayon(input: &[u8], out: &mut [u8]) {
+fn simulate_rayon(input: &[u8], out: &mut [u8], lut: &[u8; LUT_SIZE]) {
     let end = input.len().saturating_sub(2);
 
     out[1..=end].par_iter_mut().enumerate().for_each(|(idx, out_elem)| {
         let i = idx + 1;
         let cc = (input[i - 1] << 2) | (input[i] << 1) | input[i + 1];
-        *out_elem = apply_rule(cc);
+        *out_elem = apply_rule(lut, cc);
     });
 }
 
-fn simulate(input: &[u8], out: &mut [u8], indices: &[usize]) {
+fn simulate(input: &[u8], out: &mut [u8], indices: &[usize], lut: &[u8; LUT_SIZE]) {
     let end = input.len().saturating_sub(2);
     for (o, idx) in out[1..=end].iter_mut().zip(indices.iter().copied()) {
         let cc = (input[idx - 1] << 2) | (input[idx] << 1) | input[idx + 1];
-        *o = apply_rule(cc);
+        *o = apply_rule(lut, cc);
     }
 }
 
-pub fn simulate_simd(input: &[u8], out: &mut [u8]) {
+pub fn simulate_simd(input: &[u8], out: &mut [u8], lut: &[u8; LUT_SIZE]) {
     let n = input.len();
     if n < 3 {
         out.copy_from_slice(input);
@@ -54,11 +65,15 @@ pub fn simulate_simd(input: &[u8], out: &mut [u8]) {
 
         let cc = (left << Simd::splat(2)) | (mid << Simd::splat(1)) | right;
 
-        let mask = cc.simd_eq(Simd::splat(7))
-            | cc.simd_eq(Simd::splat(4))
-            | cc.simd_eq(Simd::splat(0));
-
-        let val = mask.select(Simd::splat(0u8), Simd::splat(1u8));
+        // Gather the output bits for each of the 8 possible neighborhood
+        // codes out of the lut via a select-per-code fold.
+        let mut val = Simd::<u8, 16>::splat(0);
+        for (code, &bit) in lut.iter().enumerate() {
+            if bit == 1 {
+                let mask = cc.simd_eq(Simd::splat(code as u8));
+                val = mask.select(Simd::splat(1u8), val);
+            }
+        }
         val.copy_to_slice(&mut out[i .. i + lanes]);
         i += lanes;
     }
@@ -66,7 +81,7 @@ pub fn simulate_simd(input: &[u8], out: &mut [u8]) {
     // scalar tail
     for j in i..n - 1 {
         let cc = (input[j - 1] << 2) | (input[j] << 1) | input[j + 1];
-        out[j] = apply_rule(cc);
+        out[j] = apply_rule(lut, cc);
     }
 }
 
@@ -74,19 +89,19 @@ const KERNEL_SRC: &str = r#"
 __kernel void simulate_transform(
     __global const uchar* input,
     __global uchar* output,
-    const uint n)
+    const uint n,
+    const uint rule)
 {
     uint i = get_global_id(0) + 1;
     if (i >= n - 1) return;
 
     uchar cc = (input[i - 1] << 2) | (input[i] << 1) | input[i + 1];
-    uchar val = (cc == 7 || cc == 4 || cc == 0) ? 0 : 1;
-    output[i] = val;
+    output[i] = (rule >> cc) & 1;
 }
 "#;
 
 
-pub fn simulate_ocl(input: &[u8], out: &mut [u8], iterations: usize) -> ocl::Result<()> {
+pub fn simulate_ocl(input: &[u8], out: &mut [u8], iterations: usize, rule: u8) -> ocl::Result<()> {
     assert_eq!(input.len(), out.len());
     let n = input.len();
 
@@ -105,47 +120,125 @@ pub fn simulate_ocl(input: &[u8], out: &mut [u8], iterations: usize) -> ocl::Res
         .devices(device)
         .build(&context)?;
 
-    // Create working buffers
-    let mut current = input.to_vec();
-    let mut next = vec![0u8; n];
-
-    for _ in 0..iterations {
-        let input_buf = Buffer::<u8>::builder()
-            .queue(queue.clone())
-            .flags(flags::MEM_READ_ONLY | flags::MEM_COPY_HOST_PTR)
-            .len(n)
-            .copy_host_slice(&current)
-            .build()?;
-
-        let output_buf = Buffer::<u8>::builder()
-            .queue(queue.clone())
-            .flags(flags::MEM_WRITE_ONLY)
-            .len(n)
-            .build()?;
-
-        let kernel = Kernel::builder()
-            .program(&program)
-            .name("simulate_transform")
-            .queue(queue.clone())
-            .global_work_size(n.saturating_sub(2))
-            .arg(&input_buf)
-            .arg(&output_buf)
-            .arg(&(n as u32))
-            .build()?;
+    // Two persistent device buffers, ping-ponged in place for the whole
+    // run so the host only uploads once and downloads once, instead of
+    // paying a PCIe round-trip per iteration.
+    let buf_a = Buffer::<u8>::builder()
+        .queue(queue.clone())
+        .flags(flags::MEM_READ_WRITE | flags::MEM_COPY_HOST_PTR)
+        .len(n)
+        .copy_host_slice(input)
+        .build()?;
 
-        unsafe { kernel.enq()?; }
+    // The kernel only ever writes indices 1..n-2, so whichever buffer's
+    // boundary cells (0 and n-1) aren't already zero stays non-zero
+    // forever. `buf_b` starts zeroed below, but `buf_a` was just seeded
+    // from `input` and may carry non-zero boundary cells from the caller.
+    // Those cells are still read for the first generation (as the other
+    // backends read them), and are cleared once that generation has been
+    // enqueued, so both buffers match the other backends, which always
+    // write into a zero-initialized destination.
+
+    let zeros = vec![0u8; n];
+    let buf_b = Buffer::<u8>::builder()
+        .queue(queue.clone())
+        .flags(flags::MEM_READ_WRITE | flags::MEM_COPY_HOST_PTR)
+        .len(n)
+        .copy_host_slice(&zeros)
+        .build()?;
 
-        output_buf.read(&mut next).enq()?;
-        queue.finish()?;
+    let kernel = Kernel::builder()
+        .program(&program)
+        .name("simulate_transform")
+        .queue(queue.clone())
+        .global_work_size(n.saturating_sub(2))
+        .arg(&buf_a)
+        .arg(&buf_b)
+        .arg(n as u32)
+        .arg(rule as u32)
+        .build()?;
 
-        // Swap buffers for next iteration
-        std::mem::swap(&mut current, &mut next);
+    let mut src_is_a = true;
+    for step in 0..iterations {
+        let (src, dst) = if src_is_a { (&buf_a, &buf_b) } else { (&buf_b, &buf_a) };
+        kernel.set_arg(0, src)?;
+        kernel.set_arg(1, dst)?;
+        unsafe { kernel.enq()?; }
+        if step == 0 && n > 0 {
+            buf_a.write(&[0u8][..]).offset(0).enq()?;
+            buf_a.write(&[0u8][..]).offset(n - 1).enq()?;
+        }
+        src_is_a = !src_is_a;
     }
+    queue.finish()?;
+
+    let result_buf = if src_is_a { &buf_a } else { &buf_b };
+    result_buf.read(out).enq()?;
+    queue.finish()?;
 
-    out.copy_from_slice(&current);
     Ok(())
 }
 
+/// Pack a `Vec<u8>` of 0/1 cells into one bit per cell, 64 cells per word.
+fn pack_bits(input: &[u8]) -> Vec<u64> {
+    let mut words = vec![0u64; input.len().div_ceil(64)];
+    for (i, &cell) in input.iter().enumerate() {
+        if cell == 1 {
+            words[i / 64] |= 1u64 << (i % 64);
+        }
+    }
+    words
+}
+
+/// Unpack `len` cells back out of a bit-packed word vector.
+fn unpack_bits(words: &[u64], len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|i| ((words[i / 64] >> (i % 64)) & 1) as u8)
+        .collect()
+}
+
+fn clear_bit(words: &mut [u64], i: usize) {
+    words[i / 64] &= !(1u64 << (i % 64));
+}
+
+/// Advance one packed word's worth of cells for an arbitrary neighborhood
+/// lookup table, expressed as eight bit-sliced mask combinations selected
+/// by the rule's truth table (one mask per neighborhood code).
+fn step_word(l: u64, c: u64, r: u64, lut: &[u8; LUT_SIZE]) -> u64 {
+    let mut new = 0u64;
+    for (code, &bit) in lut.iter().enumerate() {
+        if bit == 1 {
+            let lb = if code & 0b100 != 0 { l } else { !l };
+            let cb = if code & 0b010 != 0 { c } else { !c };
+            let rb = if code & 0b001 != 0 { r } else { !r };
+            new |= lb & cb & rb;
+        }
+    }
+    new
+}
+
+/// Word-parallel backend: advances a whole generation of `total_len` cells
+/// with pure bitwise ops over `Vec<u64>` words, 64 cells at a time. For
+/// Rule 110 specifically this reduces to `(c | r) & !(l & c & r)`, which
+/// `step_word`'s bit-sliced fold reproduces for the Rule 110 lookup table.
+fn simulate_bitpacked(words: &[u64], out: &mut [u64], lut: &[u8; LUT_SIZE], total_len: usize) {
+    let n = words.len();
+    for i in 0..n {
+        let c = words[i];
+        let prev = if i == 0 { 0 } else { words[i - 1] };
+        let next = if i + 1 < n { words[i + 1] } else { 0 };
+        let l = (c << 1) | (prev >> 63);
+        let r = (c >> 1) | (next << 63);
+        out[i] = step_word(l, c, r, lut);
+    }
+    // The scalar/rayon/simd backends never write the first and last cell,
+    // leaving them permanently clamped to 0; match that here.
+    if total_len > 0 {
+        clear_bit(out, 0);
+        clear_bit(out, total_len - 1);
+    }
+}
+
 fn read_input_file<P: AsRef<Path>>(p: P) -> Vec<u8> {
     let p = p.as_ref();
     if !p.exists() {
@@ -218,40 +311,402 @@ struct Args {
     /// Simulation version (policy or rayon)
     #[arg(long, value_enum)]
     version: SimulationType,
+
+    /// Wolfram elementary CA rule number (0-255), defaults to Rule 110
+    #[arg(long, default_value_t = 110)]
+    rule: u8,
+
+    /// Drop into an interactive stepping debugger instead of running to completion
+    #[arg(long)]
+    debug: bool,
+
+    /// Use the bit-packed word-parallel backend (64 cells per u64) instead of `version`
+    #[arg(long)]
+    pack: bool,
+
+    /// Write the full space-time diagram as a binary PBM (P4) image
+    #[arg(long)]
+    output: Option<String>,
+
+    /// Run all four backends on the same input and report timing/throughput
+    #[arg(long)]
+    bench: bool,
+}
+
+/// Timing/throughput summary for one backend's run, as printed by `--bench`.
+struct BenchResult {
+    label: &'static str,
+    elapsed: Duration,
+    generations: usize,
+    cells: usize,
 }
 
+/// Run `iterations` generations of `version` on a private copy of `inbuf`,
+/// timing the simulation work with wrap-safe duration accumulation so long
+/// runs can't overflow the reported total. For the CPU backends only the
+/// per-generation simulate call is timed; for `ocl` the single timed
+/// `simulate_ocl` call also includes device, program and buffer setup and
+/// the upload/download of the cells.
+fn run_bench_backend(
+    label: &'static str,
+    version: SimulationType,
+    inbuf: &[u8],
+    lut: &[u8; LUT_SIZE],
+    iterations: usize,
+    rule: u8,
+) -> BenchResult {
+    let mut buf = inbuf.to_vec();
+    let mut outbuf = vec![0u8; buf.len()];
+    let mut elapsed = Duration::ZERO;
+
+    match version {
+        SimulationType::Policy => {
+            let end = buf.len().saturating_sub(2);
+            let indices: Vec<usize> = (1..=end).collect();
+            for _ in 0..iterations {
+                let start = Instant::now();
+                simulate(&buf, &mut outbuf, &indices, lut);
+                elapsed = elapsed.saturating_add(start.elapsed());
+                buf = outbuf.clone();
+            }
+        }
+        SimulationType::Rayon => {
+            for _ in 0..iterations {
+                let start = Instant::now();
+                simulate_rayon(&buf, &mut outbuf, lut);
+                elapsed = elapsed.saturating_add(start.elapsed());
+                buf = outbuf.clone();
+            }
+        }
+        SimulationType::Simd => {
+            for _ in 0..iterations {
+                let start = Instant::now();
+                simulate_simd(&buf, &mut outbuf, lut);
+                elapsed = elapsed.saturating_add(start.elapsed());
+                buf = outbuf.clone();
+            }
+        }
+        SimulationType::Ocl => {
+            let start = Instant::now();
+            simulate_ocl(&buf, &mut outbuf, iterations, rule).unwrap();
+            elapsed = elapsed.saturating_add(start.elapsed());
+            buf = outbuf.clone();
+        }
+    }
+
+    BenchResult {
+        label,
+        elapsed,
+        generations: iterations,
+        cells: buf.len(),
+    }
+}
+
+/// `--bench` entry point: runs every `SimulationType` variant on the same
+/// input and prints a timing/throughput table.
+fn run_bench(inbuf: &[u8], lut: &[u8; LUT_SIZE], iterations: usize, rule: u8) {
+    let backends = [
+        ("policy", SimulationType::Policy),
+        ("rayon", SimulationType::Rayon),
+        ("simd", SimulationType::Simd),
+        ("ocl", SimulationType::Ocl),
+    ];
+
+    println!(
+        "{:<8} {:>12} {:>16} {:>18}",
+        "backend", "elapsed_ms", "generations/sec", "cells updated/sec"
+    );
+    for (label, version) in backends {
+        let result = run_bench_backend(label, version, inbuf, lut, iterations, rule);
+        let secs = result.elapsed.as_secs_f64();
+        let gens_per_sec = if secs > 0.0 { result.generations as f64 / secs } else { f64::INFINITY };
+        let cells_per_sec = if secs > 0.0 {
+            (result.generations * result.cells) as f64 / secs
+        } else {
+            f64::INFINITY
+        };
+        println!(
+            "{:<8} {:>12.3} {:>16.0} {:>18.0}",
+            result.label,
+            result.elapsed.as_secs_f64() * 1000.0,
+            gens_per_sec,
+            cells_per_sec
+        );
+    }
+}
+
+/// Write a space-time diagram (one row per generation) as a binary PBM
+/// (P4) image, packing 8 cells per byte with a live cell as a set bit.
+fn write_pbm<P: AsRef<Path>>(path: P, rows: &[Vec<u8>], width: usize) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    write!(file, "P4\n{} {}\n", width, rows.len())?;
+
+    let mut packed = vec![0u8; width.div_ceil(8)];
+    for row in rows {
+        packed.iter_mut().for_each(|b| *b = 0);
+        for (i, &cell) in row.iter().enumerate() {
+            if cell == 1 {
+                packed[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        file.write_all(&packed)?;
+    }
+    Ok(())
+}
+
+/// Interactive REPL state for the stepping debugger.
+struct Debugger {
+    /// The last command line that was executed (so `r` can repeat it).
+    last_command: Option<String>,
+    /// Step count used by the last `s` command; reused as the default the
+    /// next time `s` is given with no explicit count.
+    repeat: u32,
+    /// Toggled by `t`. When set, `s`/`c` print every intermediate
+    /// generation rather than just the final one reached.
+    trace_only: bool,
+}
+
+impl Debugger {
+    fn new() -> Self {
+        Debugger {
+            last_command: None,
+            repeat: 1,
+            trace_only: false,
+        }
+    }
+}
+
+fn print_row(row: &[u8]) {
+    let rendered: String = row.iter().map(|&c| if c == 1 { '1' } else { '0' }).collect();
+    let ones = row.iter().filter(|&&v| v == 1).count();
+    println!("{rendered}  ({ones} live)");
+}
+
+fn contains_pattern(row: &[u8], pattern: &[u8]) -> bool {
+    if pattern.is_empty() || pattern.len() > row.len() {
+        return false;
+    }
+    row.windows(pattern.len()).any(|w| w == pattern)
+}
+
+/// Run the interactive stepping debugger: `s [n]` steps n generations
+/// (default: the last `n` used, or 1), `c` runs until a breakpoint fires,
+/// `b <gen>` sets a generation breakpoint, `w <bitpattern>` sets a watch
+/// breakpoint on a substring of cells, `p` prints the current row, `t`
+/// toggles printing every intermediate generation during `s`/`c`, and `r`
+/// repeats the last command.
+fn run_debugger(mut inbuf: Vec<u8>, lut: &[u8; LUT_SIZE], rule: u8) {
+    let mut outbuf = vec![0u8; inbuf.len()];
+    let end = inbuf.len().saturating_sub(2);
+    let indices: Vec<usize> = (1..=end).collect();
+
+    let mut generation: u64 = 0;
+    let mut break_gen: Option<u64> = None;
+    let mut watch_pattern: Option<Vec<u8>> = None;
+    let mut debugger = Debugger::new();
+
+    println!("Interactive Rule {rule} debugger. Commands: s [n], c, b <gen>, w <bitpattern>, p, t, r");
+    print_row(&inbuf);
+
+    let stdin = io::stdin();
+    loop {
+        print!("(gen {generation}) > ");
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let trimmed = line.trim();
+
+        let command = if trimmed == "r" {
+            match debugger.last_command.clone() {
+                Some(c) => c,
+                None => {
+                    eprintln!("no previous command to repeat");
+                    continue;
+                }
+            }
+        } else if trimmed.is_empty() {
+            continue;
+        } else {
+            trimmed.to_string()
+        };
+
+        let mut parts = command.split_whitespace();
+        let cmd = parts.next().unwrap_or("");
+        match cmd {
+            "s" => {
+                let n: u32 = parts
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(debugger.repeat);
+                debugger.repeat = n;
+                for _ in 0..n {
+                    simulate(&inbuf, &mut outbuf, &indices, lut);
+                    std::mem::swap(&mut inbuf, &mut outbuf);
+                    generation += 1;
+                    if debugger.trace_only {
+                        print_row(&inbuf);
+                    }
+                    if break_gen == Some(generation) {
+                        break;
+                    }
+                }
+                print_row(&inbuf);
+            }
+            "c" => loop {
+                simulate(&inbuf, &mut outbuf, &indices, lut);
+                std::mem::swap(&mut inbuf, &mut outbuf);
+                generation += 1;
+                if debugger.trace_only {
+                    print_row(&inbuf);
+                }
+                if let Some(g) = break_gen {
+                    if generation >= g {
+                        break;
+                    }
+                }
+                if let Some(ref pattern) = watch_pattern {
+                    if contains_pattern(&inbuf, pattern) {
+                        break;
+                    }
+                }
+            },
+            "b" => {
+                break_gen = parts.next().and_then(|s| s.parse().ok());
+            }
+            "w" => {
+                watch_pattern = parts
+                    .next()
+                    .map(|pat| pat.bytes().map(|b| b.saturating_sub(b'0')).collect());
+            }
+            "p" => print_row(&inbuf),
+            "t" => {
+                debugger.trace_only = !debugger.trace_only;
+                println!("trace_only = {}", debugger.trace_only);
+            }
+            "r" => {}
+            other => eprintln!("unknown command: {other}"),
+        }
+
+        let is_c = cmd == "c";
+        if cmd != "r" {
+            debugger.last_command = Some(command);
+        }
+
+        if is_c {
+            print_row(&inbuf);
+        }
+    }
+}
 
 fn main() {
     let args = Args::parse();
 
+    let lut = build_lut(args.rule);
     let initial_vec = read_input_file(&args.init);
     let mut inbuf = initial_vec;
+
+    if args.debug {
+        run_debugger(inbuf, &lut, args.rule);
+        return;
+    }
+
+    if args.bench {
+        run_bench(&inbuf, &lut, args.iter, args.rule);
+        return;
+    }
+
+    if args.pack {
+        let total_len = inbuf.len();
+        let mut words = pack_bits(&inbuf);
+        let mut next_words = vec![0u64; words.len()];
+        let mut rows: Vec<Vec<u8>> = Vec::new();
+        if args.output.is_some() {
+            rows.push(unpack_bits(&words, total_len));
+        }
+        for _ in 0..args.iter {
+            simulate_bitpacked(&words, &mut next_words, &lut, total_len);
+            std::mem::swap(&mut words, &mut next_words);
+            if args.output.is_some() {
+                rows.push(unpack_bits(&words, total_len));
+            }
+        }
+        // `words` may carry padding bits past `total_len` in its last word;
+        // count over the unpacked, length-bounded cells so those phantom
+        // bits (which some rules evolve to 1) never inflate the population.
+        let ones = unpack_bits(&words, total_len)
+            .iter()
+            .filter(|&&v| v == 1)
+            .count();
+        println!("{ones}");
+        if let Some(path) = &args.output {
+            if let Err(e) = write_pbm(path, &rows, total_len) {
+                eprintln!("Could not write output image: {e}");
+            }
+        }
+        return;
+    }
+
     let mut outbuf = vec![0u8; inbuf.len()];
+    let mut rows: Vec<Vec<u8>> = Vec::new();
+    if args.output.is_some() {
+        rows.push(inbuf.clone());
+    }
 
     match args.version {
         SimulationType::Policy => {
             let end = inbuf.len().saturating_sub(2);
             let indices: Vec<usize> = (1..=end).collect();
             for _ in 0..args.iter {
-                simulate(&inbuf, &mut outbuf, &indices);
+                simulate(&inbuf, &mut outbuf, &indices, &lut);
                 inbuf = outbuf.clone();
+                if args.output.is_some() {
+                    rows.push(inbuf.clone());
+                }
             }
         }
         SimulationType::Rayon => {
             for _ in 0..args.iter {
-                simulate_rayon(&inbuf, &mut outbuf);
+                simulate_rayon(&inbuf, &mut outbuf, &lut);
                 inbuf = outbuf.clone();
+                if args.output.is_some() {
+                    rows.push(inbuf.clone());
+                }
             }
         }
         SimulationType::Simd => {
             for _ in 0..args.iter {
-                simulate_simd(&inbuf, &mut outbuf);
+                simulate_simd(&inbuf, &mut outbuf, &lut);
                 inbuf = outbuf.clone();
+                if args.output.is_some() {
+                    rows.push(inbuf.clone());
+                }
             }
         }
         SimulationType::Ocl => {
-            simulate_ocl(&inbuf, &mut outbuf, args.iter).unwrap();
-            inbuf = outbuf.clone();
+            if args.output.is_some() {
+                // Recording every generation needs a host-visible snapshot
+                // after each step, which means reading the device buffer
+                // back each time; run one generation per call instead of
+                // the persistent-buffer fast path so the image has the
+                // same per-generation row count as the other backends.
+                for _ in 0..args.iter {
+                    simulate_ocl(&inbuf, &mut outbuf, 1, args.rule).unwrap();
+                    inbuf = outbuf.clone();
+                    rows.push(inbuf.clone());
+                }
+            } else {
+                simulate_ocl(&inbuf, &mut outbuf, args.iter, args.rule).unwrap();
+                inbuf = outbuf.clone();
+            }
+        }
+    }
+
+    if let Some(path) = &args.output {
+        if let Err(e) = write_pbm(path, &rows, inbuf.len()) {
+            eprintln!("Could not write output image: {e}");
         }
     }
 